@@ -7,53 +7,115 @@ extern crate quote;
 extern crate guid_parser;
 extern crate chomp;
 extern crate syn;
+extern crate rand;
 
-use guid_parser::chunks;
+use guid_parser::{chunks, stamp_version4, Chunks};
 use chomp::parse_only;
-use syn::{Expr, ExprLit, Lit};
+use syn::{Expr, ExprLit, ExprPath, Lit};
+use rand::RngCore;
+
+fn is_random_keyword(path: &syn::Path) -> bool {
+    path.leading_colon.is_none() &&
+    path.segments.len() == 1 &&
+    path.segments.first().map_or(false, |segment| segment.ident == "random")
+}
+
+fn quote_parts(parts: (u32, u16, u16, [u8; 8])) -> String {
+    let data1: u32 = parts.0;
+    let data2: u16 = parts.1;
+    let data3: u16 = parts.2;
+
+    let data4_0: u8 = parts.3[0];
+    let data4_1: u8 = parts.3[1];
+    let data4_2: u8 = parts.3[2];
+    let data4_3: u8 = parts.3[3];
+    let data4_4: u8 = parts.3[4];
+    let data4_5: u8 = parts.3[5];
+    let data4_6: u8 = parts.3[6];
+    let data4_7: u8 = parts.3[7];
+
+    (quote! {
+        (#data1 as u32,
+         #data2 as u16,
+         #data3 as u16,
+         [ #data4_0 as u8,
+           #data4_1 as u8,
+           #data4_2 as u8,
+           #data4_3 as u8,
+           #data4_4 as u8,
+           #data4_5 as u8,
+           #data4_6 as u8,
+           #data4_7 as u8 ])
+    }).to_string()
+}
 
 proc_macro_expr_impl! {
     pub fn guid_parts_impl(input: &str) -> String {
         let expr = syn::parse_str::<Expr>(input).unwrap();
-        if let &Expr::Lit(ExprLit { lit: Lit::Str(ref lit), .. }) = &expr {
-            let parts = parse_only(chunks, lit.value().as_bytes()).unwrap().to_parts();
-
-            let data1: u32 = parts.0;
-            let data2: u16 = parts.1;
-            let data3: u16 = parts.2;
-
-            let data4_0: u8 = parts.3[0];
-            let data4_1: u8 = parts.3[1];
-            let data4_2: u8 = parts.3[2];
-            let data4_3: u8 = parts.3[3];
-            let data4_4: u8 = parts.3[4];
-            let data4_5: u8 = parts.3[5];
-            let data4_6: u8 = parts.3[6];
-            let data4_7: u8 = parts.3[7];
-
-            (quote! {
-                (#data1 as u32,
-                 #data2 as u16,
-                 #data3 as u16,
-                 [ #data4_0 as u8,
-                   #data4_1 as u8,
-                   #data4_2 as u8,
-                   #data4_3 as u8,
-                   #data4_4 as u8,
-                   #data4_5 as u8,
-                   #data4_6 as u8,
-                   #data4_7 as u8 ])
-            }).to_string()
-        } else {
-            panic!("illegal guid expr (expected string literal)");
+        match expr {
+            Expr::Lit(ExprLit { lit: Lit::Str(ref lit), .. }) => {
+                let parts = parse_only(chunks, lit.value().as_bytes()).unwrap().to_parts();
+                quote_parts(parts)
+            }
+            Expr::Path(ExprPath { ref path, .. }) if is_random_keyword(path) => {
+                // Draw sixteen random bytes at macro-expansion time and
+                // bake them in as a literal, so each `guid!{random}` call
+                // site gets its own fixed value, freshly drawn on every
+                // build.
+                let mut bytes = [0u8; 16];
+                rand::thread_rng().fill_bytes(&mut bytes);
+                stamp_version4(&mut bytes);
+                let parts = Chunks::from_bytes(bytes).to_parts();
+                quote_parts(parts)
+            }
+            _ => panic!("illegal guid expr (expected string literal or `random`)"),
         }
-        
     }
 }
 
 #[cfg(test)]
 mod tests {
+    use super::guid_parts_impl;
+    use syn::{Expr, ExprLit, Lit};
+
     #[test]
     fn it_works() {
     }
+
+    fn cast_to_u64(expr: &Expr) -> u64 {
+        match *expr {
+            Expr::Cast(ref cast) => match *cast.expr {
+                Expr::Lit(ExprLit { lit: Lit::Int(ref lit_int), .. }) => lit_int.value(),
+                _ => panic!("expected an integer literal, got {:?}", cast.expr)
+            },
+            _ => panic!("expected a cast expression, got {:?}", expr)
+        }
+    }
+
+    #[test]
+    fn test_random_keyword_stamps_version_and_variant() {
+        // Run this a few times since the bytes are random; the bits we're
+        // checking should be stamped in on every draw.
+        for _ in 0..8 {
+            let tokens = guid_parts_impl("random");
+            let expr: Expr = syn::parse_str(&tokens).unwrap();
+            let elems = match expr {
+                Expr::Tuple(tuple) => tuple.elems,
+                _ => panic!("expected a tuple expression, got {:?}", expr)
+            };
+
+            // `Data3`'s high byte is byte 6 of the GUID: the high nibble
+            // should be the version 4 tag.
+            let data3 = cast_to_u64(&elems[2]);
+            assert_eq!((data3 >> 8) & 0xF0, 0x40);
+
+            // `Data4[0]` is byte 8 of the GUID: the top two bits should be
+            // the RFC 4122 variant tag.
+            let data4 = match elems[3] {
+                Expr::Array(ref array) => array.elems.iter().map(cast_to_u64).collect::<Vec<_>>(),
+                _ => panic!("expected an array expression, got {:?}", elems[3])
+            };
+            assert_eq!(data4[0] & 0xC0, 0x80);
+        }
+    }
 }
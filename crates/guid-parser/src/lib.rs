@@ -75,6 +75,20 @@ pub struct Chunks {
     pub chunk5: u48
 }
 
+/// A textual form a GUID can be rendered in with `Chunks::to_string_with`,
+/// or parsed from with `chunks_any`.
+#[derive(Eq, PartialEq, Ord, PartialOrd, Clone, Copy, Debug)]
+pub enum Format {
+    /// `6B29FC40-CA47-1067-B31D-00DD010662DA`
+    Hyphenated,
+    /// `6B29FC40CA471067B31D00DD010662DA`, with no separators.
+    Simple,
+    /// `{6B29FC40-CA47-1067-B31D-00DD010662DA}`, as guidgen.exe prints it.
+    Braced,
+    /// `urn:uuid:6b29fc40-ca47-1067-b31d-00dd010662da`
+    Urn
+}
+
 impl Chunks {
     pub fn to_bytes(self) -> [u8; 16] {
         [ ((self.chunk1    & 0xFF000000) >> 24) as u8,
@@ -95,6 +109,41 @@ impl Chunks {
           ((self.chunk5.lo & 0x000000FF)      ) as u8 ]
     }
 
+    /// Build a `Chunks` from the sixteen bytes of a GUID, in the same
+    /// big-endian layout produced by `to_bytes`. Inverse of `to_bytes`.
+    ///
+    /// ```
+    /// # extern crate guid_parser;
+    /// # use guid_parser::{u48, Chunks};
+    /// # fn main() {
+    /// let bytes = [ 0x6B, 0x29, 0xFC, 0x40, 0xCA, 0x47, 0x10, 0x67,
+    ///               0xB3, 0x1D, 0x00, 0xDD, 0x01, 0x06, 0x62, 0xDA ];
+    /// assert_eq!(Chunks::from_bytes(bytes).to_bytes(), bytes);
+    /// # }
+    /// ```
+    pub fn from_bytes(b: [u8; 16]) -> Chunks {
+        Chunks {
+            chunk1: ((b[0]  as u32) << 24) |
+                    ((b[1]  as u32) << 16) |
+                    ((b[2]  as u32) <<  8) |
+                    ((b[3]  as u32)      ),
+            chunk2: ((b[4]  as u16) <<  8) |
+                    ((b[5]  as u16)      ),
+            chunk3: ((b[6]  as u16) <<  8) |
+                    ((b[7]  as u16)      ),
+            chunk4: ((b[8]  as u16) <<  8) |
+                    ((b[9]  as u16)      ),
+            chunk5: u48 {
+                hi: ((b[10] as u16) <<  8) |
+                    ((b[11] as u16)      ),
+                lo: ((b[12] as u32) << 24) |
+                    ((b[13] as u32) << 16) |
+                    ((b[14] as u32) <<  8) |
+                    ((b[15] as u32)      )
+            }
+        }
+    }
+
     pub fn to_parts(self) -> (u32, u16, u16, [u8; 8]) {
         let b = self.to_bytes();
         (self.chunk1,
@@ -103,6 +152,40 @@ impl Chunks {
          [b[8], b[9], b[10], b[11], b[12], b[13], b[14], b[15]])
     }
 
+    /// Render this `Chunks` as a GUID string in the given `Format`.
+    ///
+    /// ```
+    /// # extern crate guid_parser;
+    /// # use guid_parser::{u48, Chunks, Format};
+    /// # fn main() {
+    /// let chunks = Chunks {
+    ///     chunk1: 0x6B29FC40,
+    ///     chunk2: 0xCA47,
+    ///     chunk3: 0x1067,
+    ///     chunk4: 0xB31D,
+    ///     chunk5: u48 { hi: 0x00DD, lo: 0x010662DA }
+    /// };
+    /// assert_eq!(chunks.to_string_with(Format::Hyphenated), "6B29FC40-CA47-1067-B31D-00DD010662DA");
+    /// assert_eq!(chunks.to_string_with(Format::Simple), "6B29FC40CA471067B31D00DD010662DA");
+    /// assert_eq!(chunks.to_string_with(Format::Braced), "{6B29FC40-CA47-1067-B31D-00DD010662DA}");
+    /// assert_eq!(chunks.to_string_with(Format::Urn), "urn:uuid:6b29fc40-ca47-1067-b31d-00dd010662da");
+    /// # }
+    /// ```
+    pub fn to_string_with(self, format: Format) -> String {
+        let b = self.to_bytes();
+        let byte_range = |lo: usize, hi: usize| -> String {
+            b[lo..hi].iter().map(|byte| format!("{:02X}", byte)).collect()
+        };
+        let hyphenated = format!("{}-{}-{}-{}-{}",
+            byte_range(0, 4), byte_range(4, 6), byte_range(6, 8), byte_range(8, 10), byte_range(10, 16));
+        match format {
+            Format::Hyphenated => hyphenated,
+            Format::Simple => hyphenated.chars().filter(|&c| c != '-').collect(),
+            Format::Braced => format!("{{{}}}", hyphenated),
+            Format::Urn => format!("urn:uuid:{}", hyphenated.to_lowercase())
+        }
+    }
+
     #[cfg(windows)]
     pub fn to_guid(self) -> GUID {
         GUID {
@@ -123,12 +206,24 @@ impl Chunks {
     }
 }
 
+/// Whether a byte is a valid hex digit (`0`-`9`, `A`-`F`, or `a`-`f`).
+pub fn is_hex_digit(c: u8) -> bool {
+    b'0' <= c && c <= b'9' ||
+    b'A' <= c && c <= b'F' ||
+    b'a' <= c && c <= b'f'
+}
+
+/// Stamp the RFC 4122 version 4 and variant bits into place: the high
+/// nibble of byte 6 identifies the version, and the top two bits of byte
+/// 8 are the variant. Used by anything that mints a fresh v4 GUID from
+/// raw random bytes, whether at runtime or at macro-expansion time.
+pub fn stamp_version4(bytes: &mut [u8; 16]) {
+    bytes[6] = (bytes[6] & 0x0F) | 0x40;
+    bytes[8] = (bytes[8] & 0x3F) | 0x80;
+}
+
 fn hex_digit<I: U8Input>(i: I) -> SimpleResult<I, u8> {
-    satisfy(i, |c| {
-        b'0' <= c && c <= b'9' ||
-        b'A' <= c && c <= b'F' ||
-        b'a' <= c && c <= b'f'
-    }).map(|c| {
+    satisfy(i, |c| is_hex_digit(c)).map(|c| {
         if b'0' <= c && c <= b'9' {
             c - b'0'
         } else if b'A' <= c && c <= b'F' {
@@ -208,10 +303,60 @@ pub fn chunks<I: U8Input>(i: I) -> SimpleResult<I, Chunks> {
     }
 }
 
+/// Like `chunks`, but for the hyphen-less 32-digit form (`6B29FC40CA47...`).
+fn chunks_no_hyphens<I: U8Input>(i: I) -> SimpleResult<I, Chunks> {
+    parse!{i;
+        let chunk1 = medium_chunk();
+        let chunk2 = short_chunk();
+        let chunk3 = short_chunk();
+        let chunk4 = short_chunk();
+        let chunk5 = long_chunk();
+        ret Chunks { chunk1, chunk2, chunk3, chunk4, chunk5 }
+    }
+}
+
+/// A more permissive [chomp](https://github.com/m4rw3r/chomp) parser for
+/// GUIDs that, in addition to the strict `chunks` form, accepts a leading
+/// `{`/trailing `}` pair, a `urn:uuid:` prefix, and the hyphen-less
+/// 32-digit form. The brace pair and the `urn:uuid:` prefix are mutually
+/// exclusive, since no such combined form exists in the wild.
+///
+/// ```
+/// # extern crate chomp;
+/// # extern crate guid_parser;
+/// use chomp::prelude::*;
+/// use guid_parser::chunks_any;
+///
+/// # fn main() {
+/// let expected = parse_only(chunks_any, "6B29FC40-CA47-1067-B31D-00DD010662DA".as_bytes());
+/// assert_eq!(parse_only(chunks_any, "{6B29FC40-CA47-1067-B31D-00DD010662DA}".as_bytes()), expected);
+/// assert_eq!(parse_only(chunks_any, "urn:uuid:6b29fc40-ca47-1067-b31d-00dd010662da".as_bytes()), expected);
+/// assert_eq!(parse_only(chunks_any, "6B29FC40CA471067B31D00DD010662DA".as_bytes()), expected);
+/// # }
+/// ```
+pub fn chunks_any<I: U8Input>(i: I) -> SimpleResult<I, Chunks> {
+    option(i, |i| string(i, b"{").map(|_| true), false).bind(|i, braced| {
+        let rest = if braced {
+            i.ret(())
+        } else {
+            option(i, |i| string(i, b"urn:uuid:").map(|_| ()), ())
+        };
+        rest.bind(|i, _| {
+            or(i, chunks, chunks_no_hyphens).bind(|i, result| {
+                if braced {
+                    string(i, b"}").map(|_| result)
+                } else {
+                    i.ret(result)
+                }
+            })
+        })
+    })
+}
+
 #[cfg(test)]
 mod tests {
     use chomp::prelude::*;
-    use super::{hex_digit, short_chunk, medium_chunk, long_chunk, chunks, Chunks, u48};
+    use super::{hex_digit, short_chunk, medium_chunk, long_chunk, chunks, chunks_any, Chunks, Format, u48};
 
     #[test]
     fn test_hex_digit() {
@@ -260,6 +405,45 @@ mod tests {
             bytes);
     }
 
+    #[test]
+    fn test_from_bytes() {
+        let chunks = parse_only(chunks, b"cafef00d-CAFE-f00d-BEEF-1234abcdDADA").unwrap();
+        let bytes = chunks.to_bytes();
+        assert_eq!(Chunks::from_bytes(bytes), chunks);
+    }
+
+    #[test]
+    fn test_chunks_any_forms() {
+        let expected = Ok(Chunks {
+            chunk1: 0xcafef00d,
+            chunk2: 0xCAFE,
+            chunk3: 0xf00d,
+            chunk4: 0xBEEF,
+            chunk5: u48 {
+                hi: 0x1234,
+                lo: 0xabcdDADA
+            }
+        });
+        assert_eq!(parse_only(chunks_any, b"cafef00d-CAFE-f00d-BEEF-1234abcdDADA"), expected);
+        assert_eq!(parse_only(chunks_any, b"{cafef00d-CAFE-f00d-BEEF-1234abcdDADA}"), expected);
+        assert_eq!(parse_only(chunks_any, b"urn:uuid:cafef00d-CAFE-f00d-BEEF-1234abcdDADA"), expected);
+        assert_eq!(parse_only(chunks_any, b"cafef00dCAFEf00dBEEF1234abcdDADA"), expected);
+    }
+
+    #[test]
+    fn test_chunks_any_rejects_combined_brace_and_urn() {
+        assert!(parse_only(chunks_any, b"{urn:uuid:cafef00d-CAFE-f00d-BEEF-1234abcdDADA}").is_err());
+    }
+
+    #[test]
+    fn test_to_string_with() {
+        let chunks = parse_only(chunks, b"6B29FC40-CA47-1067-B31D-00DD010662DA").unwrap();
+        assert_eq!(chunks.to_string_with(Format::Hyphenated), "6B29FC40-CA47-1067-B31D-00DD010662DA");
+        assert_eq!(chunks.to_string_with(Format::Simple), "6B29FC40CA471067B31D00DD010662DA");
+        assert_eq!(chunks.to_string_with(Format::Braced), "{6B29FC40-CA47-1067-B31D-00DD010662DA}");
+        assert_eq!(chunks.to_string_with(Format::Urn), "urn:uuid:6b29fc40-ca47-1067-b31d-00dd010662da");
+    }
+
     #[cfg(windows)]
     #[test]
     fn test_guid() {
@@ -0,0 +1,114 @@
+//! A small command-line front end for the `guid` crate, for generating,
+//! validating, and reformatting GUIDs from the shell. Built behind the
+//! `cli` feature, since it pulls in `structopt` and `structopt_derive` as
+//! extra dependencies that library consumers of `guid` don't need.
+//! (`guid_parser` is already a dependency of `guid` itself, used
+//! unconditionally, not just by this binary.)
+
+extern crate guid;
+extern crate guid_parser;
+extern crate structopt;
+#[macro_use]
+extern crate structopt_derive;
+
+use std::process;
+use std::str::FromStr;
+
+use structopt::StructOpt;
+
+use guid_parser::Chunks;
+
+/// One of the textual forms a GUID can be reprinted in with `guid fmt`.
+/// Wraps `guid_parser::Format`, plus the C-specific `c-struct` form that
+/// doesn't belong in a general-purpose GUID string format.
+#[derive(Debug, Clone, Copy)]
+enum Format {
+    Guid(guid_parser::Format),
+    /// A `DEFINE_GUID(...)`-style C initializer.
+    CStruct
+}
+
+impl FromStr for Format {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Format, String> {
+        match s {
+            "hyphenated" => Ok(Format::Guid(guid_parser::Format::Hyphenated)),
+            "braced" => Ok(Format::Guid(guid_parser::Format::Braced)),
+            "urn" => Ok(Format::Guid(guid_parser::Format::Urn)),
+            "simple" => Ok(Format::Guid(guid_parser::Format::Simple)),
+            "c-struct" => Ok(Format::CStruct),
+            other => Err(format!(
+                "unknown format {:?} (expected hyphenated, braced, urn, simple, or c-struct)",
+                other
+            ))
+        }
+    }
+}
+
+#[derive(StructOpt)]
+#[structopt(name = "guid", about = "Generate, validate, and reformat GUIDs")]
+enum Cli {
+    /// Generate a fresh version 4 GUID
+    #[structopt(name = "new")]
+    New,
+
+    /// Parse a GUID string and print its sixteen bytes
+    #[structopt(name = "parse")]
+    Parse {
+        guid: String
+    },
+
+    /// Reprint a parsed GUID in a different textual form
+    #[structopt(name = "fmt")]
+    Fmt {
+        guid: String,
+
+        #[structopt(long = "format", default_value = "hyphenated")]
+        format: Format
+    }
+}
+
+fn format_c_struct(bytes: [u8; 16]) -> String {
+    let parts = Chunks::from_bytes(bytes).to_parts();
+    format!(
+        "DEFINE_GUID(NAME, 0x{:08X}, 0x{:04X}, 0x{:04X}, 0x{:02X}, 0x{:02X}, 0x{:02X}, 0x{:02X}, 0x{:02X}, 0x{:02X}, 0x{:02X}, 0x{:02X});",
+        parts.0, parts.1, parts.2,
+        parts.3[0], parts.3[1], parts.3[2], parts.3[3],
+        parts.3[4], parts.3[5], parts.3[6], parts.3[7]
+    )
+}
+
+fn render(format: Format, bytes: [u8; 16]) -> String {
+    match format {
+        Format::Guid(guid_format) => Chunks::from_bytes(bytes).to_string_with(guid_format),
+        Format::CStruct => format_c_struct(bytes)
+    }
+}
+
+fn parse_or_die(src: &str) -> [u8; 16] {
+    match guid::parse_bytes(src) {
+        Ok(bytes) => bytes,
+        Err(e) => {
+            eprintln!("{}", e);
+            process::exit(1);
+        }
+    }
+}
+
+fn main() {
+    match Cli::from_args() {
+        Cli::New => {
+            println!("{}", render(Format::Guid(guid_parser::Format::Hyphenated), guid::generate_v4()));
+        }
+        Cli::Parse { guid } => {
+            let bytes = parse_or_die(&guid);
+            let hex: Vec<String> = bytes.iter().map(|b| format!("{:02X}", b)).collect();
+            println!("{}", hex.join(" "));
+        }
+        Cli::Fmt { guid, format } => {
+            let bytes = parse_or_die(&guid);
+            println!("{}", render(format, bytes));
+        }
+    }
+}
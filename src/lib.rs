@@ -46,35 +46,203 @@
 //! The parser is only available to generate an array of bytes on non-Windows platforms.
 //! In Windows, this crate defines a parser that produces a `GUID` struct.
 //!
+//! `parse_bytes` and `parse` accept the canonical `8-4-4-4-12` layout as well as
+//! the same GUID wrapped in braces (`{6B29FC40-...}`), as a URN
+//! (`urn:uuid:6b29fc40-...`), or as 32 contiguous hex digits with no separators.
+//!
+//! Enabling the `cli` feature builds a `guid` binary that exposes this crate's
+//! parser and generator from the shell: `guid new`, `guid parse <string>`, and
+//! `guid fmt <string> --format {hyphenated|braced|urn|simple|c-struct}`.
+//!
 //! # Compatibility
 //!
 //! This crate supports all versions of Rust (stable and nightly) starting with Rust 1.15.
 
 extern crate chomp;
 extern crate failure;
-#[macro_use]
-extern crate failure_derive;
 extern crate guid_parser;
+extern crate rand;
+extern crate rand_chacha;
 
 use chomp::prelude::*;
 
-use guid_parser::Chunks;
+use guid_parser::{is_hex_digit, Chunks};
 
+use std::cell::RefCell;
+use std::fmt;
 use std::string::ToString;
 
+// NB: `Generator` relies on the rand-0.6-era API, where `OsRng::new()` is
+// fallible and `ChaChaCore::from_entropy()` comes from the `FromEntropy`
+// trait rather than `SeedableRng`. Pin `rand = "=0.6.5"` and
+// `rand_chacha = "=0.1.1"` when a manifest is written for this crate —
+// later releases make `OsRng` an infallible unit struct with no `new()`.
+use rand::{RngCore, FromEntropy};
+use rand::rngs::OsRng;
+use rand::rngs::adapter::ReseedingRng;
+use rand_chacha::ChaChaCore;
+
+/// The fixed `8-4-4-4-12` shape of a hyphenated GUID string, as a sequence
+/// of segments to match against: hex digit runs of the given width,
+/// alternating with single-byte separators.
+const SEGMENTS: [(usize, bool); 9] = [
+    (8, false), (1, true), (4, false), (1, true),
+    (4, false), (1, true), (4, false), (1, true), (12, false)
+];
+
+/// The shape of the hyphen-less 32-digit form: one long run of hex digits.
+const SIMPLE_SEGMENTS: [(usize, bool); 1] = [(32, false)];
+
+const URN_PREFIX: &'static str = "urn:uuid:";
+
+/// Sentinel stored in `ParseGuidError::UnexpectedChar.found` when the
+/// offending byte isn't ASCII. `diagnose` walks `src` one byte at a time,
+/// so a multi-byte UTF-8 character would otherwise get truncated to its
+/// lead or continuation byte and cast straight to the wrong `char`.
+const NON_ASCII_BYTE: char = '\u{FFFD}';
+
 /// Error returned whenever a string fails to parse as a GUID.
-#[derive(Fail, Debug)]
-#[fail(display = "{}", msg)]
-pub struct ParseGuidError {
-    /// The error message.
-    msg: String
+///
+/// Match on a variant directly to handle a particular kind of failure
+/// programmatically, or use the `Display` impl for a caret-style
+/// diagnostic pointing at the offending column of the input.
+#[derive(Debug, Eq, PartialEq, Clone)]
+pub enum ParseGuidError {
+    /// A character was found where a hex digit or separator was expected.
+    UnexpectedChar {
+        src: String,
+        offset: usize,
+        found: char,
+        expected: &'static str
+    },
+    /// The input ended before a complete GUID was read.
+    UnexpectedEnd {
+        src: String,
+        offset: usize,
+        expected: &'static str
+    },
+    /// The input contained a complete GUID followed by extra characters.
+    TrailingInput {
+        src: String,
+        offset: usize
+    }
+}
+
+impl ParseGuidError {
+    fn src(&self) -> &str {
+        match *self {
+            ParseGuidError::UnexpectedChar { ref src, .. } |
+            ParseGuidError::UnexpectedEnd { ref src, .. } |
+            ParseGuidError::TrailingInput { ref src, .. } => src
+        }
+    }
+
+    /// The byte offset into the source string at which parsing failed.
+    pub fn offset(&self) -> usize {
+        match *self {
+            ParseGuidError::UnexpectedChar { offset, .. } |
+            ParseGuidError::UnexpectedEnd { offset, .. } |
+            ParseGuidError::TrailingInput { offset, .. } => offset
+        }
+    }
+
+    fn message(&self) -> String {
+        match *self {
+            ParseGuidError::UnexpectedChar { found, expected, .. } if found == NON_ASCII_BYTE =>
+                format!("expected {}, found non-ASCII byte", expected),
+            ParseGuidError::UnexpectedChar { found, expected, .. } =>
+                format!("expected {}, found {:?}", expected, found),
+            ParseGuidError::UnexpectedEnd { expected, .. } =>
+                format!("expected {}, found end of input", expected),
+            ParseGuidError::TrailingInput { .. } =>
+                "unexpected trailing input after GUID".to_string()
+        }
+    }
+}
+
+impl fmt::Display for ParseGuidError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        writeln!(f, "{}", self.message())?;
+        writeln!(f, "{}", self.src())?;
+        write!(f, "{}^", " ".repeat(self.offset()))
+    }
+}
+
+impl failure::Fail for ParseGuidError {}
+
+/// Walk the expected shape of a GUID string by hand, to report exactly
+/// where and why it diverges. Only called once `chunks_any` has already
+/// rejected the input; `chunks_any` stays the source of truth for what's
+/// valid, this just re-derives *where* it went wrong.
+///
+/// Accepts the same surrounding forms `chunks_any` does: an optional
+/// `{...}` wrapping, an optional `urn:uuid:` prefix, and either the
+/// hyphenated or the hyphen-less digit layout.
+fn diagnose(src: &str) -> ParseGuidError {
+    let bytes = src.as_bytes();
+    let mut offset = 0;
+    let mut needs_closing_brace = false;
+
+    if bytes.first() == Some(&b'{') {
+        offset = 1;
+        needs_closing_brace = true;
+    } else if src[offset..].starts_with(URN_PREFIX) {
+        offset += URN_PREFIX.len();
+    }
+
+    // Peek past the first 8 digits to tell the hyphenated layout from the
+    // hyphen-less one, so we know which shape to hold the rest to.
+    let hyphenated = bytes.get(offset + 8) == Some(&b'-');
+    let segments: &[(usize, bool)] = if hyphenated { &SEGMENTS } else { &SIMPLE_SEGMENTS };
+
+    for &(width, is_separator) in segments.iter() {
+        for _ in 0..width {
+            let expected = if is_separator { "'-'" } else { "hex digit" };
+            match bytes.get(offset) {
+                None => return ParseGuidError::UnexpectedEnd {
+                    src: src.to_string(),
+                    offset,
+                    expected
+                },
+                Some(&c) => {
+                    let ok = if is_separator { c == b'-' } else { is_hex_digit(c) };
+                    if !ok {
+                        return ParseGuidError::UnexpectedChar {
+                            src: src.to_string(),
+                            offset,
+                            found: if c.is_ascii() { c as char } else { NON_ASCII_BYTE },
+                            expected
+                        };
+                    }
+                }
+            }
+            offset += 1;
+        }
+    }
+
+    if needs_closing_brace {
+        match bytes.get(offset) {
+            Some(&b'}') => offset += 1,
+            Some(&c) => return ParseGuidError::UnexpectedChar {
+                src: src.to_string(),
+                offset,
+                found: if c.is_ascii() { c as char } else { NON_ASCII_BYTE },
+                expected: "'}'"
+            },
+            None => return ParseGuidError::UnexpectedEnd {
+                src: src.to_string(),
+                offset,
+                expected: "'}'"
+            }
+        }
+    }
+
+    ParseGuidError::TrailingInput { src: src.to_string(), offset }
 }
 
 fn parse_chunks(src: &str) -> Result<Chunks, ParseGuidError> {
-    parse_only(guid_parser::chunks, src.as_bytes())
-        .map_err(|(_, e)| ParseGuidError {
-                    msg: e.to_string()
-                })
+    parse_only(guid_parser::chunks_any, src.as_bytes())
+        .map_err(|_| diagnose(src))
 }
 
 /// Parse a source string as a GUID, and return the GUID as a sequence of bytes.
@@ -88,6 +256,67 @@ pub fn parse(src: &str) -> Result<GUID, ParseGuidError> {
     parse_chunks(src).map(|chunks| chunks.to_guid())
 }
 
+/// The number of bytes drawn from a `Generator`'s stream before it
+/// reseeds itself from OS entropy.
+const DEFAULT_RESEED_INTERVAL: u64 = 1024 * 1024;
+
+/// A source of freshly-minted version 4 GUIDs, backed by a ChaCha CSPRNG
+/// that periodically reseeds itself from the OS entropy source.
+///
+/// Most callers should use the free function `generate_v4`, which draws
+/// from a generator with the default reseed interval. Construct a
+/// `Generator` directly to control how many bytes are drawn between
+/// reseeds, e.g. for a long-running service that wants to rotate its
+/// stream more or less often.
+pub struct Generator {
+    rng: ReseedingRng<ChaChaCore, OsRng>
+}
+
+impl Generator {
+    /// Create a generator that reseeds its CSPRNG from OS entropy every
+    /// `reseed_interval` bytes of output.
+    pub fn new(reseed_interval: u64) -> Generator {
+        let core = ChaChaCore::from_entropy();
+        let reseeder = OsRng::new().expect("failed to access OS entropy source");
+        Generator {
+            rng: ReseedingRng::new(core, reseed_interval, reseeder)
+        }
+    }
+
+    /// Generate a fresh RFC 4122 version 4 GUID as sixteen bytes.
+    pub fn generate_v4(&mut self) -> [u8; 16] {
+        let mut bytes = [0u8; 16];
+        self.rng.fill_bytes(&mut bytes);
+        guid_parser::stamp_version4(&mut bytes);
+        bytes
+    }
+}
+
+thread_local! {
+    static GENERATOR: RefCell<Generator> = RefCell::new(Generator::new(DEFAULT_RESEED_INTERVAL));
+}
+
+/// Generate a fresh RFC 4122 version 4 GUID as sixteen bytes, drawing from
+/// a thread-local `Generator` with the default reseed interval.
+///
+/// ```
+/// # extern crate guid;
+/// # fn main() {
+/// let bytes = guid::generate_v4();
+/// assert_eq!(bytes[6] & 0xF0, 0x40);
+/// assert_eq!(bytes[8] & 0xC0, 0x80);
+/// # }
+/// ```
+pub fn generate_v4() -> [u8; 16] {
+    GENERATOR.with(|g| g.borrow_mut().generate_v4())
+}
+
+#[cfg(windows)]
+/// Generate a fresh RFC 4122 version 4 GUID.
+pub fn generate_v4_guid() -> GUID {
+    Chunks::from_bytes(generate_v4()).to_guid()
+}
+
 #[cfg(windows)]
 #[macro_use]
 extern crate proc_macro_hack;
@@ -128,6 +357,21 @@ pub use winapi::guiddef::GUID;
 /// # ;
 /// # }
 /// ```
+///
+/// In place of a string literal, the keyword `random` generates a fresh
+/// version 4 GUID at compile time, which is useful for interface IDs that
+/// should be stable for a given build but don't need to match a specific
+/// value:
+///
+/// ```
+/// # #[macro_use]
+/// # extern crate guid;
+/// # use guid::GUID;
+/// # fn main() {
+/// const MY_GUID: GUID = guid!{random};
+/// # let _ = MY_GUID;
+/// # }
+/// ```
 macro_rules! guid {
     {$literal:expr} => {
         {
@@ -151,6 +395,99 @@ mod tests {
         assert_eq!(bytes, [ 0x6B, 0x29, 0xFC, 0x40, 0xCA, 0x47, 0x10, 0x67, 0xB3, 0x1D, 0x00, 0xDD, 0x01, 0x06, 0x62, 0xDA ]);
     }
 
+    #[test]
+    fn test_parser_accepts_alternate_forms() {
+        use parse_bytes;
+        let expected = parse_bytes("6B29FC40-CA47-1067-B31D-00DD010662DA").unwrap();
+        assert_eq!(parse_bytes("{6B29FC40-CA47-1067-B31D-00DD010662DA}").unwrap(), expected);
+        assert_eq!(parse_bytes("urn:uuid:6b29fc40-ca47-1067-b31d-00dd010662da").unwrap(), expected);
+        assert_eq!(parse_bytes("6B29FC40CA471067B31D00DD010662DA").unwrap(), expected);
+    }
+
+    #[test]
+    fn test_parse_error_unexpected_char() {
+        use {parse_bytes, ParseGuidError};
+        let err = parse_bytes("6B29FC40-CA47-1067-B31Z-00DD010662DA").unwrap_err();
+        match err {
+            ParseGuidError::UnexpectedChar { offset, found, .. } => {
+                assert_eq!(offset, 22);
+                assert_eq!(found, 'Z');
+            }
+            _ => panic!("expected UnexpectedChar, got {:?}", err)
+        }
+    }
+
+    #[test]
+    fn test_parse_error_unexpected_char_non_ascii() {
+        use {parse_bytes, ParseGuidError};
+        // U+2013 EN DASH in place of a separator is two UTF-8 bytes; the
+        // diagnosis should report it as a non-ASCII byte rather than
+        // misinterpreting one of those bytes as a bogus Latin-1 char.
+        let err = parse_bytes("6B29FC40-CA47-1067-B31D\u{2013}00DD010662DA").unwrap_err();
+        match err {
+            ParseGuidError::UnexpectedChar { offset, found, .. } => {
+                assert_eq!(offset, 23);
+                assert_eq!(found, '\u{FFFD}');
+            }
+            _ => panic!("expected UnexpectedChar, got {:?}", err)
+        }
+    }
+
+    #[test]
+    fn test_parse_error_unexpected_end() {
+        use {parse_bytes, ParseGuidError};
+        let err = parse_bytes("6B29FC40-CA47-1067-B31D").unwrap_err();
+        match err {
+            ParseGuidError::UnexpectedEnd { offset, .. } => {
+                assert_eq!(offset, 23);
+            }
+            _ => panic!("expected UnexpectedEnd, got {:?}", err)
+        }
+    }
+
+    #[test]
+    fn test_parse_error_trailing_input() {
+        use {parse_bytes, ParseGuidError};
+        let err = parse_bytes("6B29FC40-CA47-1067-B31D-00DD010662DA-EXTRA").unwrap_err();
+        match err {
+            ParseGuidError::TrailingInput { offset, .. } => {
+                assert_eq!(offset, 36);
+            }
+            _ => panic!("expected TrailingInput, got {:?}", err)
+        }
+    }
+
+    #[test]
+    fn test_parse_error_display_has_caret() {
+        use parse_bytes;
+        let err = parse_bytes("6B29FC40-CA47-1067-B31Z-00DD010662DA").unwrap_err();
+        let rendered = err.to_string();
+        let lines: Vec<&str> = rendered.lines().collect();
+        assert_eq!(lines[1], "6B29FC40-CA47-1067-B31Z-00DD010662DA");
+        assert_eq!(lines[2], "                      ^");
+    }
+
+    #[test]
+    fn test_generate_v4() {
+        use generate_v4;
+        for _ in 0..32 {
+            let bytes = generate_v4();
+            assert_eq!(bytes[6] & 0xF0, 0x40);
+            assert_eq!(bytes[8] & 0xC0, 0x80);
+        }
+    }
+
+    #[test]
+    fn test_generator_custom_reseed_interval() {
+        use Generator;
+        let mut gen = Generator::new(16);
+        for _ in 0..8 {
+            let bytes = gen.generate_v4();
+            assert_eq!(bytes[6] & 0xF0, 0x40);
+            assert_eq!(bytes[8] & 0xC0, 0x80);
+        }
+    }
+
     #[cfg(windows)]
     #[test]
     fn test_macro() {